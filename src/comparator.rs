@@ -0,0 +1,200 @@
+use std::{
+    cmp::Ordering,
+    slice,
+};
+
+use libc::c_int;
+
+/// A key or data ordering that can be registered with a database handle via
+/// `mdbx_dbi_open_ex` in place of libmdbx's default lexicographic byte compare.
+///
+/// # Comparators are not persisted
+///
+/// libmdbx stores only the *bytes*; it never remembers which comparator a table
+/// was opened with. Every time a named database is opened again within the
+/// environment's lifetime, the exact same `Comparator` must be supplied, or the
+/// B-tree's ordering invariant is silently violated and the table becomes
+/// corrupt. [`Database::new_with_comparators`](crate::Database::new_with_comparators)
+/// takes the comparator as part of the open parameters so that a mismatched
+/// reopen is at least a type-level decision rather than something that can be
+/// forgotten.
+pub trait Comparator {
+    /// Compares two raw key or data slices, following the same contract as
+    /// [`Ord::cmp`].
+    fn compare(a: &[u8], b: &[u8]) -> Ordering;
+}
+
+/// Builds the `extern "C"` trampoline libmdbx calls to compare two `MDBX_val`s
+/// under comparator `C`, translating them to `&[u8]` and dispatching to
+/// `C::compare`.
+///
+/// `mdbx_dbi_open_ex`'s comparator callback carries no user-data pointer, so a
+/// distinct trampoline per `Comparator` type is generated at compile time
+/// rather than dispatching on a runtime function pointer.
+pub(crate) unsafe extern "C" fn cmp_trampoline<C: Comparator>(
+    a: *const ffi::MDBX_val,
+    b: *const ffi::MDBX_val,
+) -> c_int {
+    let a = slice::from_raw_parts((*a).iov_base as *const u8, (*a).iov_len);
+    let b = slice::from_raw_parts((*b).iov_base as *const u8, (*b).iov_len);
+    match C::compare(a, b) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// Returns the `MDBX_cmp_func` libmdbx should call for comparator `C`.
+pub(crate) fn cmp_fn<C: Comparator>() -> ffi::MDBX_cmp_func {
+    Some(cmp_trampoline::<C>)
+}
+
+/// One ordering axis (key or data) passed to
+/// [`Database::new_with_comparators`](crate::Database::new_with_comparators):
+/// either a custom [`Comparator`], or [`DefaultOrder`] to keep libmdbx's
+/// default lexicographic byte ordering.
+///
+/// Encoding "default vs. custom" as a type rather than as a runtime
+/// `Option<Comparator>` means a caller can't pick a comparator type and then
+/// separately pass `None` for it and end up registering the wrong ordering (or
+/// none at all) without a type error.
+pub trait ComparatorAxis {
+    /// The `MDBX_cmp_func` to register for this axis, or `None` to keep
+    /// libmdbx's default ordering.
+    fn cmp_func() -> ffi::MDBX_cmp_func;
+}
+
+impl<C: Comparator> ComparatorAxis for C {
+    fn cmp_func() -> ffi::MDBX_cmp_func {
+        cmp_fn::<C>()
+    }
+}
+
+/// Marker axis meaning "keep libmdbx's default lexicographic byte ordering".
+/// Does not implement [`Comparator`] itself, so it can't be mistaken for one.
+pub struct DefaultOrder;
+
+impl ComparatorAxis for DefaultOrder {
+    fn cmp_func() -> ffi::MDBX_cmp_func {
+        None
+    }
+}
+
+/// Reverse-lexicographic byte ordering (the mirror image of libmdbx's default).
+pub struct ReverseComparator;
+
+impl Comparator for ReverseComparator {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// Compares keys as native-endian `u64` values. Keys shorter than 8 bytes sort
+/// before any well-formed key.
+pub struct U64NativeComparator;
+
+impl Comparator for U64NativeComparator {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        match (<[u8; 8]>::try_from(a), <[u8; 8]>::try_from(b)) {
+            (Ok(a), Ok(b)) => u64::from_ne_bytes(a).cmp(&u64::from_ne_bytes(b)),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => a.len().cmp(&b.len()),
+        }
+    }
+}
+
+/// Compares keys as native-endian `u32` values. Keys shorter than 4 bytes sort
+/// before any well-formed key.
+pub struct U32NativeComparator;
+
+impl Comparator for U32NativeComparator {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        match (<[u8; 4]>::try_from(a), <[u8; 4]>::try_from(b)) {
+            (Ok(a), Ok(b)) => u32::from_ne_bytes(a).cmp(&u32::from_ne_bytes(b)),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => a.len().cmp(&b.len()),
+        }
+    }
+}
+
+/// Compares a fixed 32-byte value as eight native-endian `u32` words, most
+/// significant word first. Useful for hash-keyed tables where the hash is
+/// stored as a sequence of machine words rather than raw big-endian bytes.
+///
+/// Values that aren't exactly 32 bytes sort before any 32-byte value, and by
+/// length among themselves, rather than panicking:
+/// a panic unwinding across the `extern "C"` trampoline libmdbx calls this
+/// through would be undefined behavior.
+pub struct Fixed32WordsComparator;
+
+impl Comparator for Fixed32WordsComparator {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        match (<[u8; 32]>::try_from(a), <[u8; 32]>::try_from(b)) {
+            (Ok(a), Ok(b)) => {
+                for i in 0..8 {
+                    let wa = u32::from_ne_bytes(a[i * 4..i * 4 + 4].try_into().unwrap());
+                    let wb = u32::from_ne_bytes(b[i * 4..i * 4 + 4].try_into().unwrap());
+                    match wa.cmp(&wb) {
+                        Ordering::Equal => continue,
+                        ord => return ord,
+                    }
+                }
+                Ordering::Equal
+            }
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => a.len().cmp(&b.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse_comparator_inverts_byte_order() {
+        assert_eq!(ReverseComparator::compare(b"a", b"b"), Ordering::Greater);
+        assert_eq!(ReverseComparator::compare(b"b", b"a"), Ordering::Less);
+        assert_eq!(ReverseComparator::compare(b"a", b"a"), Ordering::Equal);
+    }
+
+    #[test]
+    fn u64_native_comparator_orders_by_value() {
+        assert_eq!(U64NativeComparator::compare(&1u64.to_ne_bytes(), &2u64.to_ne_bytes()), Ordering::Less);
+        assert_eq!(U64NativeComparator::compare(&2u64.to_ne_bytes(), &1u64.to_ne_bytes()), Ordering::Greater);
+    }
+
+    #[test]
+    fn u64_native_comparator_does_not_panic_on_short_input() {
+        assert_eq!(U64NativeComparator::compare(&[1, 2, 3], &1u64.to_ne_bytes()), Ordering::Less);
+        assert_eq!(U64NativeComparator::compare(&[1, 2, 3], &[4, 5, 6]), Ordering::Equal);
+    }
+
+    #[test]
+    fn u32_native_comparator_orders_by_value() {
+        assert_eq!(U32NativeComparator::compare(&1u32.to_ne_bytes(), &2u32.to_ne_bytes()), Ordering::Less);
+        assert_eq!(U32NativeComparator::compare(&2u32.to_ne_bytes(), &1u32.to_ne_bytes()), Ordering::Greater);
+    }
+
+    #[test]
+    fn fixed32_words_comparator_orders_most_significant_word_first() {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        a[0..4].copy_from_slice(&1u32.to_ne_bytes());
+        b[0..4].copy_from_slice(&2u32.to_ne_bytes());
+        a[28..32].copy_from_slice(&9u32.to_ne_bytes());
+        b[28..32].copy_from_slice(&0u32.to_ne_bytes());
+        assert_eq!(Fixed32WordsComparator::compare(&a, &b), Ordering::Less);
+        assert_eq!(Fixed32WordsComparator::compare(&a, &a), Ordering::Equal);
+    }
+
+    #[test]
+    fn fixed32_words_comparator_does_not_panic_on_wrong_length() {
+        assert_eq!(Fixed32WordsComparator::compare(&[0u8; 31], &[0u8; 32]), Ordering::Less);
+        assert_eq!(Fixed32WordsComparator::compare(&[0u8; 32], &[0u8; 31]), Ordering::Greater);
+        assert_eq!(Fixed32WordsComparator::compare(&[0u8; 10], &[0u8; 20]), Ordering::Less);
+    }
+}
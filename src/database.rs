@@ -1,7 +1,13 @@
 use crate::{
+    comparator::{
+        cmp_fn,
+        Comparator,
+        ComparatorAxis,
+    },
     environment::EnvironmentKind,
     error::{
         mdbx_result,
+        Error,
         Result,
     },
     flags::{
@@ -11,6 +17,7 @@ use crate::{
     transaction::{
         txn_execute,
         TransactionKind,
+        RO,
         RW,
     },
     Cursor,
@@ -28,8 +35,91 @@ use std::{
     mem::size_of,
     ptr,
     slice,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
+/// Tracks idleness of a read-only [`Database`] handle for the opt-in
+/// reset/renew timeout mode.
+///
+/// Held behind an [`Arc`] by the handle; embedder code reads
+/// [`IdleTracker::pending_renew`] as a metric through
+/// [`Database::pending_renew_count`].
+#[derive(Debug)]
+struct IdleTracker {
+    timeout: Duration,
+    last_used: Mutex<Instant>,
+    /// Whether this handle's single reader was reset (by [`Database::reset_read`]
+    /// or [`Database::reset_if_idle`]) and is still waiting to be renewed by the
+    /// next `Database` operation. There is only ever one reader per handle, so
+    /// this is a flag, not an unbounded counter.
+    pending_renew: AtomicBool,
+}
+
+impl IdleTracker {
+    fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            last_used: Mutex::new(Instant::now()),
+            pending_renew: AtomicBool::new(false),
+        }
+    }
+
+    fn touch(&self) {
+        *self.last_used.lock() = Instant::now();
+    }
+
+    fn is_idle(&self) -> bool {
+        self.last_used.lock().elapsed() >= self.timeout
+    }
+}
+
+#[cfg(test)]
+mod idle_tracker_tests {
+    use super::*;
+
+    #[test]
+    fn not_idle_immediately_after_creation() {
+        let idle = IdleTracker::new(Duration::from_secs(60));
+        assert!(!idle.is_idle());
+    }
+
+    #[test]
+    fn idle_after_timeout_elapses() {
+        let idle = IdleTracker::new(Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(idle.is_idle());
+    }
+
+    #[test]
+    fn touch_resets_idleness() {
+        let idle = IdleTracker::new(Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(idle.is_idle());
+        idle.touch();
+        assert!(!idle.is_idle());
+    }
+
+    #[test]
+    fn pending_renew_flag_does_not_accumulate() {
+        let idle = IdleTracker::new(Duration::from_secs(60));
+        idle.pending_renew.store(true, Ordering::Release);
+        idle.pending_renew.store(true, Ordering::Release);
+        assert!(idle.pending_renew.load(Ordering::Acquire));
+        idle.pending_renew.store(false, Ordering::Release);
+        assert!(!idle.pending_renew.load(Ordering::Acquire));
+    }
+}
+
 /// A handle to an individual database in an environment.
 ///
 /// A database handle denotes the name and parameters of a database in an environment.
@@ -40,6 +130,7 @@ where
 {
     dbi: ffi::MDBX_dbi,
     txn: &'txn Mutex<*mut ffi::MDBX_txn>,
+    idle: Option<Arc<IdleTracker>>,
     _marker: PhantomData<&'txn K>,
 }
 
@@ -67,18 +158,84 @@ where
         Ok(Database {
             dbi,
             txn: txn.txn_mutex(),
+            idle: None,
             _marker: PhantomData,
         })
     }
 
+    /// Opens a new database handle with custom key and/or data orderings, via
+    /// `mdbx_dbi_open_ex`. This is the public entry point for the feature: call
+    /// it directly with whatever `Transaction` you already have (there is no
+    /// separate `Environment`/`RwTransaction` sugar layered on top of it).
+    /// Prefer [`Database::new_with_comparators`] unless you already have raw
+    /// `MDBX_cmp_func`s to register.
+    ///
+    /// # Comparators are not persisted
+    ///
+    /// See [`Comparator`]: the same comparator must be supplied every time this
+    /// named database is opened within the environment's lifetime, or the
+    /// B-tree ordering invariant recorded on disk is corrupted.
+    pub fn new_with_cmp<'env, E: EnvironmentKind>(
+        txn: &'txn Transaction<'env, K, E>,
+        name: Option<&str>,
+        flags: c_uint,
+        key_cmp: ffi::MDBX_cmp_func,
+        data_cmp: ffi::MDBX_cmp_func,
+    ) -> Result<Self> {
+        let c_name = name.map(|n| CString::new(n).unwrap());
+        let name_ptr = if let Some(c_name) = &c_name {
+            c_name.as_ptr()
+        } else {
+            ptr::null()
+        };
+        let mut dbi: ffi::MDBX_dbi = 0;
+        mdbx_result(txn_execute(txn.txn_mutex(), |txn| unsafe {
+            ffi::mdbx_dbi_open_ex(txn, name_ptr, flags, &mut dbi, key_cmp, data_cmp)
+        }))?;
+        Ok(Database {
+            dbi,
+            txn: txn.txn_mutex(),
+            idle: None,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Like [`Database::new_with_cmp`], but takes the key and data orderings as
+    /// [`ComparatorAxis`] type parameters (a [`Comparator`], or
+    /// [`comparator::DefaultOrder`](crate::comparator::DefaultOrder) to keep
+    /// libmdbx's default lexicographic byte ordering on that axis) instead of
+    /// raw `MDBX_cmp_func`s. The comparator is picked entirely by `KC`/`DC`, so
+    /// there's no separate runtime flag that could disagree with the type.
+    pub fn new_with_comparators<'env, E: EnvironmentKind, KC: ComparatorAxis, DC: ComparatorAxis>(
+        txn: &'txn Transaction<'env, K, E>,
+        name: Option<&str>,
+        flags: c_uint,
+    ) -> Result<Self> {
+        Self::new_with_cmp(txn, name, flags, KC::cmp_func(), DC::cmp_func())
+    }
+
     pub(crate) fn freelist_db<'env, E: EnvironmentKind>(txn: &'txn Transaction<'env, K, E>) -> Self {
         Database {
             dbi: 0,
             txn: txn.txn_mutex(),
+            idle: None,
             _marker: PhantomData,
         }
     }
 
+    /// If this handle is in idle-timeout mode and its reader was reset for
+    /// exceeding the timeout, transparently renews it before `self` is used.
+    /// A no-op for handles not in timeout mode.
+    fn ensure_fresh(&self) -> Result<()> {
+        if let Some(idle) = &self.idle {
+            if idle.pending_renew.swap(false, Ordering::AcqRel) {
+                mdbx_result(txn_execute(self.txn, |txn| unsafe { ffi::mdbx_txn_renew(txn) }))?;
+            }
+            idle.touch();
+        }
+        Ok(())
+    }
+
     /// Returns the underlying MDBX database handle.
     ///
     /// The caller **must** ensure that the handle is not used after the lifetime of the
@@ -93,11 +250,13 @@ where
 
     /// Open a new cursor on the given database.
     pub fn cursor(&self) -> Result<Cursor<'txn, K>> {
+        self.ensure_fresh()?;
         Cursor::new(self)
     }
 
     /// Gets the option flags for the given database in the transaction.
     pub fn db_flags(&self) -> Result<DatabaseFlags> {
+        self.ensure_fresh()?;
         let mut flags: c_uint = 0;
         unsafe {
             mdbx_result(txn_execute(self.txn, |txn| {
@@ -109,6 +268,7 @@ where
 
     /// Retrieves database statistics.
     pub fn stat(&self) -> Result<Stat> {
+        self.ensure_fresh()?;
         unsafe {
             let mut stat = Stat::new();
             mdbx_result(txn_execute(self.txn, |txn| {
@@ -117,9 +277,241 @@ where
             Ok(stat)
         }
     }
+
+    /// Page and entry totals for this table, derived from [`Database::stat`],
+    /// in the shape storage dashboards want for "table pages" / "table entries"
+    /// gauges. Pair with [`Database::total_free_pages`] on the GC/freelist
+    /// handle (see [`Database::freelist_db`]) for a "freelist" gauge.
+    pub fn page_totals(&self) -> Result<PageTotals> {
+        let stat = self.stat()?;
+        Ok(PageTotals {
+            branch_pages: stat.branch_pages() as u64,
+            leaf_pages: stat.leaf_pages() as u64,
+            overflow_pages: stat.overflow_pages() as u64,
+            entries: stat.entries() as u64,
+        })
+    }
+
+    /// Sums the reclaimable page counts recorded in the GC/freelist table.
+    ///
+    /// Only meaningful on the handle returned by [`Database::freelist_db`] (the
+    /// reserved `dbi == 0` GC table): each record's value is a packed array of
+    /// 32-bit `pgno_t` page numbers whose first element is the count of page
+    /// numbers that follow it, so this walks every record with `MDBX_NEXT` and
+    /// accumulates that leading count.
+    pub fn total_free_pages(&self) -> Result<u64> {
+        self.ensure_fresh()?;
+        let mut total = 0u64;
+        let mut key_val: ffi::MDBX_val = ffi::MDBX_val {
+            iov_len: 0,
+            iov_base: ptr::null_mut(),
+        };
+        let mut data_val: ffi::MDBX_val = ffi::MDBX_val {
+            iov_len: 0,
+            iov_base: ptr::null_mut(),
+        };
+
+        mdbx_result(txn_execute(self.txn, |txn| unsafe {
+            let mut cursor: *mut ffi::MDBX_cursor = ptr::null_mut();
+            let rc = ffi::mdbx_cursor_open(txn, self.dbi(), &mut cursor);
+            if rc != ffi::MDBX_SUCCESS {
+                return rc;
+            }
+
+            let mut rc = ffi::mdbx_cursor_get(cursor, &mut key_val, &mut data_val, ffi::MDBX_cursor_op::MDBX_FIRST);
+            while rc == ffi::MDBX_SUCCESS {
+                let record = slice::from_raw_parts(data_val.iov_base as *const u8, data_val.iov_len);
+                total += gc_record_free_page_count(record);
+                rc = ffi::mdbx_cursor_get(cursor, &mut key_val, &mut data_val, ffi::MDBX_cursor_op::MDBX_NEXT);
+            }
+            ffi::mdbx_cursor_close(cursor);
+
+            if rc == ffi::MDBX_NOTFOUND {
+                ffi::MDBX_SUCCESS
+            } else {
+                rc
+            }
+        }))?;
+
+        Ok(total)
+    }
+
+    /// Reads the current value of this database's persistent sequence counter
+    /// (see [`Database::sequence`]) without allocating from it.
+    pub fn sequence_peek(&self) -> Result<u64> {
+        self.ensure_fresh()?;
+        let mut result: u64 = 0;
+        mdbx_result(txn_execute(self.txn, |txn| unsafe { ffi::mdbx_dbi_sequence(txn, self.dbi(), &mut result, 0) }))?;
+        Ok(result)
+    }
+}
+
+/// Reads the leading `pgno_t` (a 32-bit page number, regardless of host word
+/// size) count-of-free-pages element from a single GC/freelist record's raw
+/// value, or `0` if the record is too short to hold one. Pulled out of
+/// [`Database::total_free_pages`] so the packed-format parsing can be
+/// exercised without a live environment.
+fn gc_record_free_page_count(record: &[u8]) -> u64 {
+    if record.len() < size_of::<u32>() {
+        return 0;
+    }
+    // SAFETY: just checked `record` holds at least one `pgno_t`.
+    (unsafe { ptr::read_unaligned(record.as_ptr() as *const u32) }) as u64
+}
+
+#[cfg(test)]
+mod gc_record_tests {
+    use super::*;
+
+    #[test]
+    fn reads_leading_word_as_free_page_count() {
+        let count: u32 = 42;
+        assert_eq!(gc_record_free_page_count(&count.to_ne_bytes()), 42);
+    }
+
+    #[test]
+    fn ignores_trailing_page_numbers() {
+        let mut record = 7u32.to_ne_bytes().to_vec();
+        record.extend_from_slice(&[0xaa; 24]);
+        assert_eq!(gc_record_free_page_count(&record), 7);
+    }
+
+    #[test]
+    fn too_short_record_counts_as_zero() {
+        assert_eq!(gc_record_free_page_count(&[1, 2, 3]), 0);
+        assert_eq!(gc_record_free_page_count(&[]), 0);
+    }
+}
+
+/// Page and entry totals for a single table, suitable for emitting as storage
+/// metrics (e.g. "table pages", "table entries").
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PageTotals {
+    pub branch_pages: u64,
+    pub leaf_pages: u64,
+    pub overflow_pages: u64,
+    pub entries: u64,
+}
+
+impl PageTotals {
+    /// Total number of pages (branch + leaf + overflow) backing this table.
+    pub fn total_pages(&self) -> u64 {
+        self.branch_pages + self.leaf_pages + self.overflow_pages
+    }
+}
+
+impl<'txn> Database<'txn, RO> {
+    /// Opens a new database handle whose read transaction can be reset and
+    /// renewed via an opt-in idle timeout, in addition to the manual
+    /// [`Database::reset_read`] / [`Database::renew_read`] pair.
+    ///
+    /// After `idle_timeout` elapses without activity, a caller may reset the
+    /// reader with [`Database::reset_if_idle`]. The reader is then
+    /// transparently renewed by the next call to [`Database::cursor`],
+    /// [`Database::stat`], [`Database::db_flags`], [`Database::total_free_pages`],
+    /// or [`Database::sequence_peek`] — every `Database` read operation defined
+    /// on this handle. There is no `Database::get`; reads that go through
+    /// `Transaction::get` directly are outside this handle and are not renewed
+    /// by it.
+    pub fn new_with_idle_timeout<'env, E: EnvironmentKind>(
+        txn: &'txn Transaction<'env, RO, E>,
+        name: Option<&str>,
+        flags: c_uint,
+        idle_timeout: Duration,
+    ) -> Result<Self> {
+        let mut database = Self::new(txn, name, flags)?;
+        database.idle = Some(Arc::new(IdleTracker::new(idle_timeout)));
+        Ok(database)
+    }
+
+    /// Releases this read transaction's reader-table slot and MVCC snapshot via
+    /// `mdbx_txn_reset`, while keeping this handle and its cached `dbi` valid.
+    ///
+    /// The snapshot is gone once this returns: any `&[u8]` previously read
+    /// through this transaction must not be dereferenced afterwards. This is
+    /// **not** enforced by the type system — those borrows are tied to the
+    /// `Transaction`, not to this `&mut self`, and other `Database` handles
+    /// opened from the same transaction share it without taking a mutable
+    /// borrow here. Callers are responsible for ensuring no such borrow is
+    /// still live across a reset. Call [`Database::renew_read`] before using
+    /// this handle again.
+    pub fn reset_read(&mut self) -> Result<()> {
+        mdbx_result(txn_execute(self.txn, |txn| unsafe { ffi::mdbx_txn_reset(txn) }))?;
+        if let Some(idle) = &self.idle {
+            idle.pending_renew.store(true, Ordering::Release);
+            idle.touch();
+        }
+        Ok(())
+    }
+
+    /// Re-acquires a fresh MVCC snapshot after [`Database::reset_read`] (or
+    /// after an idle-timeout reset). Fails cleanly if the environment has since
+    /// been closed.
+    pub fn renew_read(&mut self) -> Result<()> {
+        mdbx_result(txn_execute(self.txn, |txn| unsafe { ffi::mdbx_txn_renew(txn) }))?;
+        if let Some(idle) = &self.idle {
+            idle.pending_renew.store(false, Ordering::Release);
+            idle.touch();
+        }
+        Ok(())
+    }
+
+    /// In idle-timeout mode (see [`Database::new_with_idle_timeout`]), resets
+    /// this reader if it has been idle longer than the configured timeout and
+    /// reports whether it did so. A reset reader is automatically renewed by
+    /// the next `Database` operation (`cursor`, `stat`, ...).
+    ///
+    /// Returns `false` without resetting anything for handles not in
+    /// idle-timeout mode, or while the handle is still within its timeout.
+    pub fn reset_if_idle(&mut self) -> Result<bool> {
+        let is_idle = match &self.idle {
+            Some(idle) => idle.is_idle(),
+            None => return Ok(false),
+        };
+        if !is_idle {
+            return Ok(false);
+        }
+        self.reset_read()?;
+        Ok(true)
+    }
+
+    /// Number of readers in idle-timeout mode that were reset (by
+    /// [`Database::reset_if_idle`] or [`Database::reset_read`]) but that have
+    /// not yet been renewed by a subsequent operation. Embedders in
+    /// idle-timeout mode can surface this as a metric.
+    /// There is only ever one reader per handle, so this is `0` or `1`.
+    pub fn pending_renew_count(&self) -> usize {
+        self.idle
+            .as_ref()
+            .is_some_and(|idle| idle.pending_renew.load(Ordering::Acquire)) as usize
+    }
 }
 
 impl<'txn> Database<'txn, RW> {
+    /// Atomically reserves `increment` values from this database's persistent
+    /// sequence counter (`mdbx_dbi_sequence`) and returns the value the counter
+    /// held *before* the reservation, i.e. the first free value in the newly
+    /// reserved range. A zero `increment` just reads the current value, like
+    /// [`Database::sequence_peek`].
+    ///
+    /// The reservation participates in the current write transaction: if the
+    /// transaction aborts, the reserved range is released back. Fails with the
+    /// underlying libmdbx error if the counter would overflow `u64::MAX`.
+    pub fn sequence(&self, increment: u64) -> Result<u64> {
+        let mut result: u64 = 0;
+        // `mdbx_dbi_sequence` signals overflow by returning `MDBX_RESULT_TRUE`
+        // rather than a negative error code, which `mdbx_result` maps to
+        // `Ok(true)`; turn that into an explicit error instead of treating the
+        // (stale) `result` as a successfully reserved value.
+        let overflowed = mdbx_result(txn_execute(self.txn, |txn| unsafe {
+            ffi::mdbx_dbi_sequence(txn, self.dbi(), &mut result, increment)
+        }))?;
+        if overflowed {
+            return Err(Error::from_err_code(ffi::MDBX_RESULT_TRUE));
+        }
+        Ok(result)
+    }
+
     /// Returns a buffer which can be used to write a value into the item at the
     /// given key and with the given length. The buffer must be completely
     /// filled by the caller.
@@ -171,6 +563,95 @@ impl<'txn> Database<'txn, RW> {
         Ok(())
     }
 
+    /// Deletes every key in `[start, end)`, returning the number of rows removed.
+    ///
+    /// For `DUPSORT` tables all duplicates of a matched key are removed and each
+    /// counts towards the returned total, i.e. the whole key disappears rather
+    /// than just its first value. This opens an internal cursor and walks
+    /// forward from `start`, so it avoids the O(n) round-trips a caller-side
+    /// loop over [`Database::del`] would need.
+    pub fn del_range(&self, start: impl AsRef<[u8]>, end: impl AsRef<[u8]>) -> Result<usize> {
+        let end = end.as_ref();
+        self.del_while(start.as_ref(), |key| key < end)
+    }
+
+    /// Deletes every key starting with `prefix`, returning the number of rows
+    /// removed. See [`Database::del_range`] for `DUPSORT` behavior.
+    pub fn del_prefix(&self, prefix: impl AsRef<[u8]>) -> Result<usize> {
+        let prefix = prefix.as_ref();
+        self.del_while(prefix, |key| key.starts_with(prefix))
+    }
+
+    /// Seeks to the first key `>= first`, then deletes rows in ascending order
+    /// for as long as `keep_going(key)` holds, returning the total number of
+    /// rows removed (duplicates included).
+    ///
+    /// After each delete this re-seeks with `MDBX_SET_RANGE` on the deleted
+    /// key's own bytes (copied out beforehand, since the delete can invalidate
+    /// or relocate the memory `key_val` pointed at) rather than trusting
+    /// `MDBX_NEXT` to land on the right row relative to the cursor's
+    /// post-delete position.
+    fn del_while(&self, first: &[u8], mut keep_going: impl FnMut(&[u8]) -> bool) -> Result<usize> {
+        let mut removed = 0usize;
+        let mut seek_key = first.to_vec();
+        let mut key_val: ffi::MDBX_val = ffi::MDBX_val {
+            iov_len: seek_key.len(),
+            iov_base: seek_key.as_ptr() as *mut c_void,
+        };
+        let mut data_val: ffi::MDBX_val = ffi::MDBX_val {
+            iov_len: 0,
+            iov_base: ptr::null_mut(),
+        };
+
+        mdbx_result(txn_execute(self.txn, |txn| unsafe {
+            let mut cursor: *mut ffi::MDBX_cursor = ptr::null_mut();
+            let rc = ffi::mdbx_cursor_open(txn, self.dbi(), &mut cursor);
+            if rc != ffi::MDBX_SUCCESS {
+                return rc;
+            }
+
+            let mut rc = ffi::mdbx_cursor_get(cursor, &mut key_val, &mut data_val, ffi::MDBX_cursor_op::MDBX_SET_RANGE);
+            while rc == ffi::MDBX_SUCCESS {
+                let key = slice::from_raw_parts(key_val.iov_base as *const u8, key_val.iov_len);
+                if !keep_going(key) {
+                    break;
+                }
+
+                seek_key.clear();
+                seek_key.extend_from_slice(key);
+
+                // Number of duplicates at the current key (1 for non-DUPSORT
+                // tables); learned before the delete below removes them.
+                let mut dup_count: usize = 1;
+                if ffi::mdbx_cursor_count(cursor, &mut dup_count) != ffi::MDBX_SUCCESS {
+                    dup_count = 1;
+                }
+
+                // MDBX_ALLDUPS removes every duplicate for the current key on
+                // DUPSORT tables, and is ignored (whole-row delete) otherwise.
+                let del_rc = ffi::mdbx_cursor_del(cursor, ffi::MDBX_ALLDUPS);
+                if del_rc != ffi::MDBX_SUCCESS {
+                    ffi::mdbx_cursor_close(cursor);
+                    return del_rc;
+                }
+                removed += dup_count;
+
+                key_val.iov_len = seek_key.len();
+                key_val.iov_base = seek_key.as_ptr() as *mut c_void;
+                rc = ffi::mdbx_cursor_get(cursor, &mut key_val, &mut data_val, ffi::MDBX_cursor_op::MDBX_SET_RANGE);
+            }
+            ffi::mdbx_cursor_close(cursor);
+
+            if rc == ffi::MDBX_NOTFOUND {
+                ffi::MDBX_SUCCESS
+            } else {
+                rc
+            }
+        }))?;
+
+        Ok(removed)
+    }
+
     /// Empties the given database. All items will be removed.
     pub fn clear_db(&self) -> Result<()> {
         mdbx_result(txn_execute(self.txn, |txn| unsafe { ffi::mdbx_drop(txn, self.dbi(), false) }))?;
@@ -191,3 +672,30 @@ impl<'txn> Database<'txn, RW> {
 
 unsafe impl<'txn, K> Send for Database<'txn, K> where K: TransactionKind {}
 unsafe impl<'txn, K> Sync for Database<'txn, K> where K: TransactionKind {}
+
+#[cfg(test)]
+mod del_while_tests {
+    // `del_range`/`del_prefix` delegate their row-selection predicates to plain
+    // slice operations; exercised directly here since driving the cursor/FFI
+    // loop itself needs a live environment.
+
+    #[test]
+    fn del_range_predicate_is_half_open() {
+        let end = b"c".as_slice();
+        let keep_going = |key: &[u8]| key < end;
+        assert!(keep_going(b"a"));
+        assert!(keep_going(b"b"));
+        assert!(!keep_going(b"c"));
+        assert!(!keep_going(b"d"));
+    }
+
+    #[test]
+    fn del_prefix_predicate_matches_only_prefixed_keys() {
+        let prefix = b"ab".as_slice();
+        let keep_going = |key: &[u8]| key.starts_with(prefix);
+        assert!(keep_going(b"ab"));
+        assert!(keep_going(b"abc"));
+        assert!(!keep_going(b"a"));
+        assert!(!keep_going(b"b"));
+    }
+}